@@ -6,24 +6,68 @@
 //! the results in JSON.  The server relies on the same tax law and
 //! calculator definitions used by the core engine.
 
+use crate::auth::{load_roles, require_auth, Role, RoleMap};
 use crate::engine::run_payroll;
-use crate::models::{PayRunInput, PayRunResult};
-use crate::tax::{load_tax_laws_from_dir, FlatStateCalculator, TaxLaw, TaxCalculator, UsFederalCalculator};
+use crate::models::{FxRates, PayRunInput, PayRunResult};
+use crate::tax::{load_tax_laws_from_dir, load_tax_laws_report, TaxLaw};
 use anyhow::Result;
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+use axum::{
+    extract::State, http::StatusCode, middleware, response::IntoResponse, routing::post, Extension,
+    Json, Router,
+};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// The hot-reloadable tables.  Grouping the tax laws and FX rates under
+/// one lock lets `/api/reload` swap them together under a single write
+/// guard, so a concurrent request never observes new laws with stale
+/// rates (or vice versa).
+pub struct Tables {
+    pub tax_laws: HashMap<String, TaxLaw>,
+    /// Exchange rates used to report payroll figures in a common base currency.
+    pub fx: FxRates,
+}
+
 /// Application state shared across requests.
 pub struct AppState {
-    pub tax_laws: RwLock<HashMap<String, TaxLaw>>,
-    pub calculators: RwLock<HashMap<String, Arc<dyn TaxCalculator>>>,
+    /// The reloadable tax-law and FX tables, swapped atomically on reload.
+    pub tables: RwLock<Tables>,
+    /// Credential→role map governing access to the API.
+    pub auth: RoleMap,
+    /// Directory the tax laws and FX table were loaded from, retained
+    /// so administrative routes can reload them.
+    pub tax_law_dir: PathBuf,
+}
+
+/// Load the FX-rate table that sits alongside the tax-law files.
+///
+/// The table is read from `fx_rates.json` in the tax-law directory.
+/// When the file is absent the engine falls back to the default
+/// USD-based table with no conversions, so a single-currency
+/// deployment needs no extra configuration.
+pub fn load_fx_rates(tax_law_dir: &std::path::Path) -> FxRates {
+    let path = tax_law_dir.join("fx_rates.json");
+    match std::fs::read_to_string(&path) {
+        Ok(data) => match serde_json::from_str::<FxRates>(&data) {
+            Ok(mut rates) => {
+                if rates.source.is_empty() {
+                    rates.source = path.display().to_string();
+                }
+                rates
+            }
+            Err(err) => {
+                eprintln!("Failed to parse FX rates {:?}: {}", path, err);
+                FxRates::default()
+            }
+        },
+        Err(_) => FxRates::default(),
+    }
 }
 
-/// Build the API router and initialise tax laws/calculators from the
-/// given directory.  Returns the router and a handle to the state.
+/// Build the API router and initialise tax laws from the given
+/// directory.  Returns the router and a handle to the state.
 pub async fn build_router(tax_law_dir: PathBuf) -> Result<(Router, Arc<AppState>)> {
     // Load tax laws from disk
     let laws = load_tax_laws_from_dir(&tax_law_dir)?;
@@ -31,28 +75,22 @@ pub async fn build_router(tax_law_dir: PathBuf) -> Result<(Router, Arc<AppState>
     for law in laws.into_iter() {
         law_map.insert(format!("{}-{}", law.region, law.version), law);
     }
-    // Build calculators; register at least a federal calculator as a fallback
-    let mut calculators: HashMap<String, Arc<dyn TaxCalculator>> = HashMap::new();
-    calculators.insert("US-FED".to_string(), Arc::new(UsFederalCalculator));
-    // Example: register state calculators for each region found in the tax laws
-    let regions: Vec<String> = law_map
-        .values()
-        .map(|law| law.region.clone())
-        .collect();
-    for region in regions {
-        // Avoid registering the federal calculator twice
-        if region == "US-FED" {
-            continue;
-        }
-        calculators.insert(region.clone(), Arc::new(FlatStateCalculator { region }));
-    }
+    let fx = load_fx_rates(&tax_law_dir);
     let state = Arc::new(AppState {
-        tax_laws: RwLock::new(law_map),
-        calculators: RwLock::new(calculators),
+        tables: RwLock::new(Tables {
+            tax_laws: law_map,
+            fx,
+        }),
+        auth: load_roles(),
+        tax_law_dir,
     });
-    // Construct router
+    // Construct router.  Every route sits behind the auth middleware,
+    // which authenticates the caller and records their role; handlers
+    // then enforce the per-role authorization rules.
     let router = Router::new()
         .route("/api/calculate", post(calculate_handler))
+        .route("/api/reload", post(reload_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth))
         .with_state(state.clone());
     Ok((router, state))
 }
@@ -60,13 +98,32 @@ pub async fn build_router(tax_law_dir: PathBuf) -> Result<(Router, Arc<AppState>
 /// Handler for POST /api/calculate
 async fn calculate_handler(
     State(app_state): State<Arc<AppState>>,
+    Extension(role): Extension<Role>,
     Json(input): Json<PayRunInput>,
 ) -> impl IntoResponse {
-    // Clone tax laws and calculators under read lock for this request
-    let tax_laws = app_state.tax_laws.read().await;
-    let calculators = app_state.calculators.read().await;
-    match run_payroll(input, &*tax_laws, &*calculators) {
-        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    // Read-only callers may not trigger a pay run.
+    if !role.can_run_payroll() {
+        return (StatusCode::FORBIDDEN, "insufficient role for this operation").into_response();
+    }
+    // Hold the tables under a single read lock for this request so the
+    // laws and FX rates seen are always a consistent snapshot.
+    let tables = app_state.tables.read().await;
+    // Seed the run with any prior YTD totals supplied by the caller so
+    // wage-base caps and surtaxes accumulate across requests; the updated
+    // totals are returned for the caller to persist and feed back.
+    let prior_ytd = input.ytd.clone();
+    match run_payroll(input, &tables.tax_laws, &prior_ytd, &tables.fx) {
+        Ok((result, ytd)) => {
+            // Describe the reporting currency and rate source so the
+            // response stands on its own for cross-border consumers.
+            let body = serde_json::json!({
+                "result": result,
+                "base_currency": tables.fx.base,
+                "fx_source": tables.fx.source,
+                "ytd": ytd,
+            });
+            (StatusCode::OK, Json(body)).into_response()
+        }
         Err(err) => {
             let body = Json(serde_json::json!({"error": err.to_string()}));
             (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
@@ -74,6 +131,51 @@ async fn calculate_handler(
     }
 }
 
+/// Handler for POST /api/reload (admin only).
+///
+/// Re-runs the tax-law and FX loaders and swaps the reloadable tables
+/// under a single write guard so tax-table updates take effect without a
+/// restart and without a concurrent request observing a torn mix of old
+/// and new tables.  Rule files are validated against the strict
+/// [`crate::tax::TaxRules`] schema; the response reports how many laws
+/// loaded and names any files that failed validation.
+async fn reload_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(role): Extension<Role>,
+) -> impl IntoResponse {
+    if !role.can_admin() {
+        return (StatusCode::FORBIDDEN, "admin role required").into_response();
+    }
+    let report = match load_tax_laws_report(&app_state.tax_law_dir) {
+        Ok(report) => report,
+        Err(err) => {
+            let body = Json(serde_json::json!({"error": err.to_string()}));
+            return (StatusCode::INTERNAL_SERVER_ERROR, body).into_response();
+        }
+    };
+    let mut law_map = HashMap::new();
+    for law in report.laws.into_iter() {
+        law_map.insert(format!("{}-{}", law.region, law.version), law);
+    }
+    let count = law_map.len();
+    let fx = load_fx_rates(&app_state.tax_law_dir);
+    // Swap the laws and FX rates together under a single write guard so a
+    // concurrent request never sees a torn mix of old and new tables.
+    {
+        let mut tables = app_state.tables.write().await;
+        tables.tax_laws = law_map;
+        tables.fx = fx;
+    }
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "loaded": count,
+            "failed": report.failures,
+        })),
+    )
+        .into_response()
+}
+
 /// Launch the API server.  This function builds the router from the
 /// given tax law directory and binds to the supplied address.  It
 /// blocks until the server terminates (e.g. when interrupted).
@@ -0,0 +1,121 @@
+//! Authentication and role‑based authorization for the API.
+//!
+//! Payroll data is sensitive, so every API request must present a
+//! credential — an API key (`X-API-Key` header) or a bearer token
+//! (`Authorization: Bearer …`).  Each credential maps to a [`Role`]
+//! that governs which operations the caller may perform.  The
+//! credential→role map is loaded from configuration at start‑up so
+//! deployments can rotate keys without a code change.
+
+use crate::api::AppState;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The access level granted to a credential.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Full access, including administrative routes such as reload.
+    Admin,
+    /// May submit pay runs but not perform administrative actions.
+    PayrollRunner,
+    /// May only read; rejected from any mutating route.
+    ReadOnly,
+}
+
+impl Role {
+    /// Whether this role may submit a pay run.
+    pub fn can_run_payroll(self) -> bool {
+        matches!(self, Role::Admin | Role::PayrollRunner)
+    }
+
+    /// Whether this role may perform administrative actions.
+    pub fn can_admin(self) -> bool {
+        matches!(self, Role::Admin)
+    }
+}
+
+/// Maps credential strings (API keys or bearer tokens) to their role.
+pub type RoleMap = HashMap<String, Role>;
+
+/// Load the credential→role map from configuration.
+///
+/// The map is read from the file named by `WAGE_AUTH_FILE` if set,
+/// otherwise from the inline JSON in `WAGE_AUTH_KEYS`.  Both encode a
+/// JSON object of `{"<credential>": "<role>"}`.  When neither is
+/// present an empty map is returned, so every request is rejected as
+/// unauthenticated (`401`); there is no setting for unauthenticated
+/// access.
+pub fn load_roles() -> RoleMap {
+    let data = if let Ok(path) = std::env::var("WAGE_AUTH_FILE") {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            Err(err) => {
+                eprintln!("Failed to read auth file {}: {}", path, err);
+                None
+            }
+        }
+    } else {
+        std::env::var("WAGE_AUTH_KEYS").ok()
+    };
+    match data {
+        Some(contents) => match serde_json::from_str::<RoleMap>(&contents) {
+            Ok(map) => map,
+            Err(err) => {
+                eprintln!("Failed to parse auth configuration: {}", err);
+                RoleMap::new()
+            }
+        },
+        None => RoleMap::new(),
+    }
+}
+
+/// Extract the presented credential from a request's headers.
+///
+/// Recognises both `Authorization: Bearer <token>` and `X-API-Key:
+/// <key>`.
+fn credential_from_request(req: &Request<Body>) -> Option<String> {
+    if let Some(value) = req.headers().get("authorization") {
+        if let Ok(value) = value.to_str() {
+            if let Some(token) = value.strip_prefix("Bearer ") {
+                return Some(token.trim().to_string());
+            }
+        }
+    }
+    if let Some(value) = req.headers().get("x-api-key") {
+        if let Ok(value) = value.to_str() {
+            return Some(value.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Middleware that authenticates a request and records its [`Role`] in
+/// the request extensions for downstream handlers to authorize against.
+///
+/// Returns `401 Unauthorized` when no recognised credential is present.
+/// Handlers are responsible for the finer‑grained authorization check
+/// (e.g. rejecting [`Role::ReadOnly`] from a pay run with `403`).
+pub async fn require_auth(
+    State(state): State<Arc<AppState>>,
+    mut req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let role = credential_from_request(&req)
+        .and_then(|cred| state.auth.get(&cred).copied());
+    match role {
+        Some(role) => {
+            req.extensions_mut().insert(role);
+            next.run(req).await
+        }
+        None => (StatusCode::UNAUTHORIZED, "missing or invalid credentials").into_response(),
+    }
+}
@@ -7,29 +7,60 @@
 //! [`TaxCalculator`] trait, allowing each region to define its own
 //! logic.
 
-use crate::models::{EmployeePayResult, PayItem, PayRunInput, PayRunResult};
-use crate::tax::{TaxCalculator, TaxLaw};
+use crate::models::{
+    EmployeePayResult, FxRates, PayItem, PayRunInput, PayRunResult, YtdAccumulator, YtdTotals,
+};
+use crate::tax::{calculator_for, TaxLaw};
 use anyhow::{anyhow, Result};
 use rayon::prelude::*;
 use serde_json::json;
 use std::collections::HashMap;
-use std::sync::Arc;
 
-/// Runs a payroll for a given input and tax calculators.
+/// Selects the tax law in effect for `region` on the given ISO date.
 ///
-/// `tax_laws` is a map from region codes to tax law definitions.
-/// `calculators` maps region codes to the appropriate tax calculator.
+/// The law map may contain several versions of the same region (keyed
+/// by `"{region}-{version}"`).  Among the laws whose `region` matches
+/// and whose effective window contains `date`, the one with the
+/// greatest `version` string is returned so that a newer revision wins
+/// on ties.  Returns `None` when no version covers the date.
+fn select_law<'a>(
+    tax_laws: &'a HashMap<String, TaxLaw>,
+    region: &str,
+    date: &str,
+) -> Option<&'a TaxLaw> {
+    tax_laws
+        .values()
+        .filter(|law| law.region == region && law.is_effective_on(date))
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+/// Runs a payroll for a given input and tax laws.
+///
+/// `tax_laws` is a map from `"{region}-{version}"` keys to tax law
+/// definitions.  The calculator for each employee is instantiated from
+/// the law version selected for the pay period (see [`calculator_for`]),
+/// so dispatch always matches the chosen version's rules shape.
+/// `ytd` carries each employee's prior year‑to‑date totals so that
+/// wage‑base‑capped and surtax calculations can account for cumulative
+/// earnings; it is returned updated alongside the [`PayRunResult`] so
+/// the caller can persist it for the next run.
+///
+/// `fx` supplies the exchange rates used to report each employee's
+/// gross, taxes and net in the common base currency alongside their
+/// local‑currency figures.
 pub fn run_payroll(
     input: PayRunInput,
     tax_laws: &HashMap<String, TaxLaw>,
-    calculators: &HashMap<String, Arc<dyn TaxCalculator>>,
-) -> Result<PayRunResult> {
+    ytd: &YtdAccumulator,
+    fx: &FxRates,
+) -> Result<(PayRunResult, YtdAccumulator)> {
     // Clone the inputs required inside the parallel closure
     let period = input.pay_period.clone();
     let pay_items = input.pay_items.clone();
 
-    // Compute each employee's pay result in parallel
-    let results: Vec<EmployeePayResult> = input
+    // Compute each employee's pay result in parallel, carrying each
+    // employee's updated YTD totals alongside the result.
+    let computed: Vec<(EmployeePayResult, (String, YtdTotals))> = input
         .employees
         .into_par_iter()
         .map(|employee| {
@@ -54,40 +85,112 @@ pub fn run_payroll(
                     employee.pay_rate
                 }
             };
-            let extra: f64 = pay_items
-                .get(&employee.id)
-                .map(|items| {
-                    items
-                        .iter()
-                        .filter(|i| i.description.to_lowercase() != "hours")
-                        .map(|i| i.amount)
-                        .sum::<f64>()
-                })
-                .unwrap_or(0.0);
-            let gross = base_gross + extra;
-            // Determine tax law for employee's home region, defaulting to zero tax
-            let law = tax_laws.get(&employee.home_region).or_else(|| tax_laws.get("US-FED"));
-            let calculator = calculators.get(&employee.home_region).or_else(|| calculators.get("US-FED"));
-            let taxes = if let (Some(l), Some(calc)) = (law, calculator) {
-                calc.calculate(&employee, gross, l)
+            // Split the non-"hours" pay items by category.  Earnings add
+            // to gross; pre-tax deductions shrink the taxable base; post-tax
+            // deductions come out of net only; employer contributions are
+            // tracked for reporting but touch neither gross nor net.
+            use crate::models::PayItemCategory;
+            let mut earnings_total = 0.0;
+            let mut pre_tax_total = 0.0;
+            let mut post_tax_total = 0.0;
+            let mut employer_total = 0.0;
+            if let Some(items) = pay_items.get(&employee.id) {
+                for item in items {
+                    if item.description.to_lowercase() == "hours" {
+                        continue;
+                    }
+                    match item.category {
+                        PayItemCategory::Earning => earnings_total += item.amount,
+                        PayItemCategory::PreTaxDeduction => pre_tax_total += item.amount,
+                        PayItemCategory::PostTaxDeduction => post_tax_total += item.amount,
+                        PayItemCategory::EmployerContribution => employer_total += item.amount,
+                    }
+                }
+            }
+            let gross = base_gross + earnings_total;
+            // Pre-tax deductions reduce the base passed to the calculator.
+            let taxable_gross = (gross - pre_tax_total).max(0.0);
+            // Determine the tax law in effect for this employee's region on
+            // the pay period's start date.  The law map is keyed by
+            // `"{region}-{version}"`, so several versions of the same region
+            // may coexist; we pick the one whose effective window contains the
+            // period start, breaking ties in favour of the newest version.
+            let law = select_law(tax_laws, &employee.home_region, &period.start)
+                .or_else(|| select_law(tax_laws, "US-FED", &period.start));
+            // Prior YTD totals for this employee seed wage-base and surtax logic.
+            let prior = ytd.get(&employee.id).cloned().unwrap_or_default();
+            // Build the calculator from the selected law so a bracket/capped
+            // version is never served by a sibling version's flat calculator.
+            let taxes = if let Some(l) = law {
+                calculator_for(l).calculate_with_ytd(&employee, taxable_gross, l, &prior)
             } else {
                 0.0
             };
-            let net = gross - taxes;
-            // Build details JSON; for demonstration we include just the tax rate if available
-            let details = if let Some(l) = law {
+            // Net pay is gross less pre-tax deductions, taxes and post-tax
+            // deductions.  Employer contributions do not affect net.
+            let net = gross - pre_tax_total - taxes - post_tax_total;
+            // Build details JSON; include the tax region/version plus the
+            // gross/taxable-gross/deduction breakdown so downstream systems
+            // can reconcile how the taxable base was derived.
+            let mut details = if let Some(l) = law {
                 json!({"tax_region": l.region, "tax_version": l.version})
             } else {
                 json!({})
             };
-            EmployeePayResult {
-                employee,
-                gross,
-                taxes,
-                net,
-                details,
+            if let Some(obj) = details.as_object_mut() {
+                obj.insert("gross".to_string(), json!(gross));
+                obj.insert("taxable_gross".to_string(), json!(taxable_gross));
+                obj.insert("pre_tax_total".to_string(), json!(pre_tax_total));
+                obj.insert("post_tax_total".to_string(), json!(post_tax_total));
+                obj.insert("employer_total".to_string(), json!(employer_total));
+                // Report base-currency equivalents so cross-border teams
+                // can reconcile every employee against a common currency.
+                // When the currency is absent from the FX table we flag the
+                // gap rather than fabricating a 1:1 conversion.
+                obj.insert("currency".to_string(), json!(employee.currency));
+                obj.insert("base_currency".to_string(), json!(fx.base));
+                match fx.rate_for(&employee.currency) {
+                    Some(rate) => {
+                        obj.insert("fx_rate".to_string(), json!(rate));
+                        obj.insert("gross_base".to_string(), json!(gross * rate));
+                        obj.insert("taxes_base".to_string(), json!(taxes * rate));
+                        obj.insert("net_base".to_string(), json!(net * rate));
+                    }
+                    None => {
+                        obj.insert("fx_rate".to_string(), json!(null));
+                        obj.insert("fx_rate_missing".to_string(), json!(true));
+                    }
+                }
             }
+            // Roll this period's tax into the employee's YTD totals.  We
+            // accumulate the *taxable* gross — the same quantity we fed the
+            // calculator — so wage-base caps bind against a consistent base.
+            let mut updated = prior;
+            updated.gross += taxable_gross;
+            let region_key = law
+                .map(|l| l.region.clone())
+                .unwrap_or_else(|| employee.home_region.clone());
+            *updated.tax_by_region.entry(region_key).or_insert(0.0) += taxes;
+            let employee_id = employee.id.clone();
+            (
+                EmployeePayResult {
+                    employee,
+                    gross,
+                    taxes,
+                    net,
+                    details,
+                },
+                (employee_id, updated),
+            )
         })
         .collect();
-    Ok(PayRunResult { period, results })
+
+    // Split the per-employee results from their updated YTD totals.
+    let mut updated_ytd = ytd.clone();
+    let mut results = Vec::with_capacity(computed.len());
+    for (result, (id, totals)) in computed {
+        updated_ytd.insert(id, totals);
+        results.push(result);
+    }
+    Ok((PayRunResult { period, results }, updated_ytd))
 }
\ No newline at end of file
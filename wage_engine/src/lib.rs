@@ -8,4 +8,5 @@
 pub mod models;
 pub mod tax;
 pub mod engine;
+pub mod auth;
 pub mod api;
\ No newline at end of file
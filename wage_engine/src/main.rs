@@ -21,4 +21,4 @@ async fn main() {
 }
 
 // Public re-exports so the binary has access to library modules
-pub use wage_engine::{api, engine, models, tax};
\ No newline at end of file
+pub use wage_engine::{api, auth, engine, models, tax};
\ No newline at end of file
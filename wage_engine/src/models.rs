@@ -28,6 +28,16 @@ pub struct Employee {
     /// How the employee is paid (hourly or salaried).  See
     /// [`PayFrequency`] for details.
     pub pay_frequency: PayFrequency,
+    /// ISO 4217 currency code in which this employee is paid (e.g.
+    /// `"USD"`, `"EUR"`).  When omitted the employee is assumed to be
+    /// paid in US dollars, matching the engine's historical behaviour.
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+/// The currency assumed for employees whose record omits `currency`.
+fn default_currency() -> String {
+    "USD".to_string()
 }
 
 /// Indicates whether an employee is paid hourly or on a salaried basis.
@@ -54,6 +64,38 @@ pub struct PayItem {
     /// Monetary value of this item.  Positive values represent
     /// earnings; negative values represent deductions.
     pub amount: f64,
+    /// How this item participates in the payroll calculation.  See
+    /// [`PayItemCategory`].  When omitted the item defaults to a plain
+    /// [`PayItemCategory::Earning`], preserving the historical
+    /// signed‑amount behaviour where every item simply added to gross.
+    /// For the two deduction categories `amount` is a positive magnitude
+    /// that the engine subtracts.
+    #[serde(default)]
+    pub category: PayItemCategory,
+}
+
+/// Classifies how a [`PayItem`] affects gross, taxable income and net
+/// pay.
+///
+/// Pre‑tax deductions (401(k), health premiums) shrink the taxable
+/// base before withholding is computed; post‑tax deductions come out
+/// of net pay only; employer contributions are reported for the record
+/// but are part of neither gross nor net.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayItemCategory {
+    /// Taxable earnings such as overtime, bonuses or reimbursements.
+    #[default]
+    Earning,
+    /// A deduction taken before tax, reducing the taxable base (e.g. a
+    /// traditional 401(k) contribution or pre‑tax health premium).
+    PreTaxDeduction,
+    /// A deduction taken after tax, reducing net pay only (e.g. a Roth
+    /// 401(k) contribution or a garnishment).
+    PostTaxDeduction,
+    /// An employer‑side contribution that is tracked for reporting but
+    /// excluded from both gross and net pay.
+    EmployerContribution,
 }
 
 /// Defines the start and end dates of a pay period.  Dates are
@@ -81,6 +123,13 @@ pub struct PayRunInput {
     pub pay_items: HashMap<String, Vec<PayItem>>,
     /// The period over which payment is being calculated.
     pub pay_period: PayPeriod,
+    /// Prior year‑to‑date totals per employee, carried in so that
+    /// wage‑base‑capped and surtax calculations accumulate across runs.
+    /// Defaults to empty for the first run of the year; the updated
+    /// totals are returned in the run result for the caller to persist
+    /// and feed back on the next request.
+    #[serde(default)]
+    pub ytd: YtdAccumulator,
 }
 
 /// The result of a payroll calculation for a single employee.
@@ -102,6 +151,73 @@ pub struct EmployeePayResult {
     pub details: serde_json::Value,
 }
 
+/// Cumulative year‑to‑date totals for a single employee.
+///
+/// Several payroll taxes (the Social Security wage base, the additional
+/// Medicare surtax) depend on earnings accumulated across earlier pay
+/// runs, which a stateless engine cannot express.  Callers persist a
+/// [`YtdAccumulator`] of these totals between runs and hand it back to
+/// the engine on the next run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct YtdTotals {
+    /// Taxable gross accumulated so far this year.  This is the same
+    /// quantity the engine passes to the calculator (gross less pre‑tax
+    /// deductions), so wage‑base caps accumulate against a consistent
+    /// base across runs.
+    pub gross: f64,
+    /// Tax withheld so far this year, keyed by region code.
+    pub tax_by_region: HashMap<String, f64>,
+}
+
+/// Maps employee IDs to their [`YtdTotals`].  Passed into the engine to
+/// seed wage‑base and surtax calculations and returned updated so the
+/// caller can persist it for the next run.
+pub type YtdAccumulator = HashMap<String, YtdTotals>;
+
+/// A table of foreign‑exchange rates used to report payroll figures in
+/// a common base currency.
+///
+/// Each entry in `rates` maps an ISO 4217 currency code to the value of
+/// **one unit of that currency expressed in the base currency**, so a
+/// local amount is converted to the base simply by multiplying by the
+/// rate.  The base currency itself always converts at `1.0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxRates {
+    /// The base (reporting) currency, e.g. `"USD"`.
+    pub base: String,
+    /// Conversion rates from each currency into the base currency.
+    #[serde(default)]
+    pub rates: HashMap<String, f64>,
+    /// Human‑readable description of where these rates came from (a file
+    /// path, provider name, etc.), surfaced in the API response.
+    #[serde(default)]
+    pub source: String,
+}
+
+impl Default for FxRates {
+    fn default() -> Self {
+        FxRates {
+            base: "USD".to_string(),
+            rates: HashMap::new(),
+            source: "none".to_string(),
+        }
+    }
+}
+
+impl FxRates {
+    /// Returns the rate that converts one unit of `currency` into the
+    /// base currency, or `None` when the currency is not in the table.
+    /// The base currency always converts at `1.0`.  Callers should treat
+    /// `None` as a reporting gap to flag rather than silently assuming
+    /// parity, which would emit wrong base‑currency figures.
+    pub fn rate_for(&self, currency: &str) -> Option<f64> {
+        if currency == self.base {
+            return Some(1.0);
+        }
+        self.rates.get(currency).copied()
+    }
+}
+
 /// The aggregate result of a payroll run.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayRunResult {
@@ -5,10 +5,11 @@
 //! individual jurisdictions implement, and helper types for loading
 //! tax law from versioned JSON files.
 
-use crate::models::{Employee, PayFrequency};
+use crate::models::{Employee, PayFrequency, YtdTotals};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::Arc;
 
 /// Represents the tax law for a particular region at a specific
 /// version.  Tax laws are expected to be stored externally as JSON
@@ -23,10 +24,42 @@ pub struct TaxLaw {
     /// Version string, e.g. `"2025"` or `"2024-Q1"`.  Versions
     /// correspond to named JSON files stored under `tax_laws/`.
     pub version: String,
+    /// Inclusive ISO 8601 (`YYYY-MM-DD`) date on which this version of
+    /// the law takes effect.  When omitted the law is treated as
+    /// effective from the beginning of time, so a single undated file
+    /// behaves as it always has.
+    #[serde(default)]
+    pub effective_from: Option<String>,
+    /// Inclusive ISO 8601 (`YYYY-MM-DD`) date after which this version
+    /// of the law no longer applies.  When omitted the law is treated
+    /// as effective indefinitely.
+    #[serde(default)]
+    pub effective_to: Option<String>,
     /// Arbitrary JSON data containing tax rules for this region.
     pub rules: Value,
 }
 
+impl TaxLaw {
+    /// Returns true when this law is in effect for the given pay-period
+    /// start date.  ISO 8601 `YYYY-MM-DD` strings sort lexicographically
+    /// in chronological order, so plain string comparison suffices.  An
+    /// absent `effective_from`/`effective_to` leaves that side of the
+    /// window open.
+    pub fn is_effective_on(&self, date: &str) -> bool {
+        if let Some(from) = &self.effective_from {
+            if date < from.as_str() {
+                return false;
+            }
+        }
+        if let Some(to) = &self.effective_to {
+            if date > to.as_str() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 /// A tax calculator determines how much tax to withhold from a gross
 /// amount for a given employee.  Each jurisdiction (state, province,
 /// country) should provide its own implementation.
@@ -42,28 +75,142 @@ pub trait TaxCalculator: Send + Sync {
     /// [`TaxLaw`] to compute the amount.  The returned value should
     /// represent the total tax withheld.
     fn calculate(&self, employee: &Employee, gross: f64, law: &TaxLaw) -> f64;
+
+    /// Calculates the tax for this pay period given the employee's prior
+    /// [`YtdTotals`].  This is the entry point the engine uses so that
+    /// wage‑base‑capped and surtax calculations can see cumulative
+    /// earnings.  The default implementation ignores the YTD totals and
+    /// delegates to [`TaxCalculator::calculate`], so stateless
+    /// calculators need not override it.
+    fn calculate_with_ytd(
+        &self,
+        employee: &Employee,
+        gross: f64,
+        law: &TaxLaw,
+        _prior: &YtdTotals,
+    ) -> f64 {
+        self.calculate(employee, gross, law)
+    }
 }
 
-/// Load all tax law definitions from a directory.
+/// Strictly‑typed view of a [`TaxLaw`]'s `rules` field.
 ///
-/// This helper scans a directory and attempts to parse any `.json`
-/// files as [`TaxLaw`] objects.  The returned vector contains one
-/// entry per file.  Duplicate region/version combinations are not
-/// checked; if you need deduplication you should perform it on the
-/// caller side.
-pub fn load_tax_laws_from_dir(path: &std::path::Path) -> Result<Vec<TaxLaw>> {
-    let mut laws = Vec::new();
+/// Tax laws are still carried as a flexible `serde_json::Value` so that
+/// calculators can read the fields they need, but at load time each
+/// file's rules are validated against this enum.  Every variant uses
+/// `#[serde(deny_unknown_fields)]`, so a misspelled or malformed rule
+/// file fails validation — and is reported by name — rather than
+/// silently behaving like a zero‑tax region.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TaxRules {
+    /// Graduated brackets consumed by [`BracketCalculator`].
+    Brackets(BracketRules),
+    /// Wage‑base‑capped rules consumed by [`CappedCalculator`].
+    Capped(CappedRules),
+    /// A single flat rate consumed by [`UsFederalCalculator`] and
+    /// [`FlatStateCalculator`].
+    Flat(FlatRules),
+}
+
+/// A single flat `"rate"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FlatRules {
+    pub rate: f64,
+}
+
+/// Graduated‑bracket rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BracketRules {
+    #[serde(default)]
+    pub standard_deduction: Option<f64>,
+    #[serde(default)]
+    pub periods_per_year: Option<f64>,
+    pub brackets: Vec<BracketBand>,
+}
+
+/// One band of a [`BracketRules`] schedule; a `null`/omitted `upto`
+/// marks the top band.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BracketBand {
+    #[serde(default)]
+    pub upto: Option<f64>,
+    pub rate: f64,
+}
+
+/// Wage‑base‑capped rules with an optional high‑earner surtax.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CappedRules {
+    pub rate: f64,
+    pub wage_base: f64,
+    #[serde(default)]
+    pub additional_rate: Option<f64>,
+    #[serde(default)]
+    pub threshold: Option<f64>,
+}
+
+/// A tax-law file that failed to load or validate, named so an operator
+/// can find and fix it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadFailure {
+    /// The offending file.
+    pub file: String,
+    /// The parse or validation error.
+    pub error: String,
+}
+
+/// The outcome of scanning a tax-law directory: the laws that loaded
+/// cleanly plus a list of files that failed validation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadReport {
+    /// Laws that parsed and validated successfully.
+    pub laws: Vec<TaxLaw>,
+    /// Files rejected during loading.
+    pub failures: Vec<LoadFailure>,
+}
+
+/// Load all tax law definitions from a directory, validating each
+/// file's rules against [`TaxRules`].
+///
+/// Files that fail to parse as a [`TaxLaw`] or whose `rules` do not
+/// match a known schema are collected into [`LoadReport::failures`]
+/// rather than loaded, so a bad file is surfaced by name instead of
+/// silently behaving like a zero‑tax region.  Duplicate region/version
+/// combinations are not checked; deduplicate on the caller side if
+/// required.
+pub fn load_tax_laws_report(path: &std::path::Path) -> Result<LoadReport> {
+    let mut report = LoadReport::default();
     if path.is_dir() {
         for entry in std::fs::read_dir(path)? {
             let entry = entry?;
             if entry.file_type()?.is_file() {
                 if let Some(ext) = entry.path().extension() {
                     if ext == "json" {
+                        let file = entry.path().display().to_string();
                         let data = std::fs::read_to_string(entry.path())?;
                         match serde_json::from_str::<TaxLaw>(&data) {
-                            Ok(law) => laws.push(law),
+                            Ok(law) => {
+                                // Validate the rules against the strict schema.
+                                if let Err(err) =
+                                    serde_json::from_value::<TaxRules>(law.rules.clone())
+                                {
+                                    report.failures.push(LoadFailure {
+                                        file,
+                                        error: err.to_string(),
+                                    });
+                                } else {
+                                    report.laws.push(law);
+                                }
+                            }
                             Err(err) => {
-                                eprintln!("Failed to parse tax law {:?}: {}", entry.path(), err);
+                                report.failures.push(LoadFailure {
+                                    file,
+                                    error: err.to_string(),
+                                });
                             }
                         }
                     }
@@ -71,7 +218,21 @@ pub fn load_tax_laws_from_dir(path: &std::path::Path) -> Result<Vec<TaxLaw>> {
             }
         }
     }
-    Ok(laws)
+    Ok(report)
+}
+
+/// Load all tax law definitions from a directory.
+///
+/// This is a thin wrapper over [`load_tax_laws_report`] that returns
+/// only the successfully validated laws; any failures are logged to
+/// stderr.  Callers that need to surface the failures (e.g. the reload
+/// endpoint) should use [`load_tax_laws_report`] directly.
+pub fn load_tax_laws_from_dir(path: &std::path::Path) -> Result<Vec<TaxLaw>> {
+    let report = load_tax_laws_report(path)?;
+    for failure in &report.failures {
+        eprintln!("Failed to load tax law {}: {}", failure.file, failure.error);
+    }
+    Ok(report.laws)
 }
 
 /// A very simple example tax calculator for US federal taxes.  It
@@ -123,6 +284,210 @@ impl TaxCalculator for FlatStateCalculator {
     }
 }
 
+/// A progressive (graduated) bracket tax calculator.
+///
+/// Unlike [`UsFederalCalculator`] and [`FlatStateCalculator`], which
+/// apply a single flat `"rate"`, this calculator interprets the
+/// `rules` field as a set of graduated brackets.  The expected schema
+/// is:
+///
+/// ```json
+/// {
+///   "standard_deduction": 14600.0,
+///   "periods_per_year": 26.0,
+///   "brackets": [
+///     {"upto": 11600.0, "rate": 0.10},
+///     {"upto": 47150.0, "rate": 0.12},
+///     {"upto": null,    "rate": 0.22}
+///   ]
+/// }
+/// ```
+///
+/// The final bracket uses `null` (or an omitted) `upto` to represent
+/// the top, unbounded band.  The per-period gross is annualised, the
+/// standard deduction is applied (floored at zero), the annual tax is
+/// accumulated band by band, and the result is divided back down to a
+/// per-period withholding amount.  If the `brackets` key is absent the
+/// calculator degrades to the flat `"rate"` behaviour so that existing
+/// tax-law files keep working unchanged.
+pub struct BracketCalculator {
+    pub region: String,
+}
+
+/// Default number of pay periods per year when the tax law omits
+/// `periods_per_year` (biweekly payroll).
+const DEFAULT_PERIODS_PER_YEAR: f64 = 26.0;
+
+impl TaxCalculator for BracketCalculator {
+    fn region_code(&self) -> &str {
+        &self.region
+    }
+
+    fn calculate(&self, _employee: &Employee, gross: f64, law: &TaxLaw) -> f64 {
+        // Without a `brackets` array we cannot do graduated withholding,
+        // so fall back to the simple flat-rate behaviour shared by the
+        // other calculators.  This keeps flat tax-law files working.
+        let brackets = match law.rules.get("brackets").and_then(|v| v.as_array()) {
+            Some(b) => b,
+            None => {
+                let rate = law
+                    .rules
+                    .get("rate")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                return gross * rate;
+            }
+        };
+
+        // A missing or zero `periods_per_year` falls back to biweekly.
+        let periods_per_year = law
+            .rules
+            .get("periods_per_year")
+            .and_then(|v| v.as_f64())
+            .filter(|p| *p > 0.0)
+            .unwrap_or(DEFAULT_PERIODS_PER_YEAR);
+        let standard_deduction = law
+            .rules
+            .get("standard_deduction")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0);
+
+        // Annualise, apply the standard deduction, and floor at zero.
+        let annual_gross = gross * periods_per_year;
+        let income = (annual_gross - standard_deduction).max(0.0);
+
+        // Collect the bands as (upto, rate) pairs.  A band with a
+        // missing or null `upto` is the top band and is modelled as
+        // positive infinity.  Bands are sorted ascending so that the
+        // accumulation below sees them in order regardless of file
+        // ordering.
+        let mut bands: Vec<(f64, f64)> = brackets
+            .iter()
+            .map(|b| {
+                let upto = b
+                    .get("upto")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(f64::INFINITY);
+                let rate = b.get("rate").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                (upto, rate)
+            })
+            .collect();
+        bands.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Walk the bands accumulating tax on the portion of income that
+        // falls within each band.
+        let mut annual_tax = 0.0;
+        let mut prev_upto = 0.0;
+        for (upto, rate) in bands {
+            if income <= prev_upto {
+                break;
+            }
+            let band_top = income.min(upto);
+            annual_tax += (band_top - prev_upto) * rate;
+            prev_upto = upto;
+        }
+
+        annual_tax / periods_per_year
+    }
+}
+
+/// A wage‑base‑capped tax calculator with an optional high‑earner
+/// surtax.
+///
+/// This models taxes such as Social Security, which stops once an
+/// employee's year‑to‑date earnings reach a wage base, and the
+/// additional Medicare tax, which adds a surtax above a threshold.  The
+/// expected schema is:
+///
+/// ```json
+/// {
+///   "rate": 0.062,
+///   "wage_base": 168600.0,
+///   "additional_rate": 0.009,
+///   "threshold": 200000.0
+/// }
+/// ```
+///
+/// `wage_base` is required by the [`TaxRules`] schema that rule files
+/// are validated against at load time; the surtax fields are optional,
+/// and absent surtax fields disable the surtax.  Because the cap depends on
+/// cumulative earnings, this calculator only behaves correctly via
+/// [`TaxCalculator::calculate_with_ytd`]; calling the stateless
+/// [`TaxCalculator::calculate`] assumes no prior earnings this year.
+pub struct CappedCalculator {
+    pub region: String,
+}
+
+impl TaxCalculator for CappedCalculator {
+    fn region_code(&self) -> &str {
+        &self.region
+    }
+
+    fn calculate(&self, employee: &Employee, gross: f64, law: &TaxLaw) -> f64 {
+        // No prior YTD context: treat this as the first run of the year.
+        self.calculate_with_ytd(employee, gross, law, &YtdTotals::default())
+    }
+
+    fn calculate_with_ytd(
+        &self,
+        _employee: &Employee,
+        gross: f64,
+        law: &TaxLaw,
+        prior: &YtdTotals,
+    ) -> f64 {
+        let rate = law.rules.get("rate").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let prior_ytd = prior.gross;
+        let new_ytd = prior_ytd + gross;
+
+        // Only the portion of this period's earnings that falls below the
+        // wage base is taxed at the base rate.  An absent wage base means
+        // the rate applies to the whole period.
+        let wage_base = law
+            .rules
+            .get("wage_base")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(f64::INFINITY);
+        let taxable = (new_ytd.min(wage_base) - prior_ytd).max(0.0);
+        let mut tax = taxable * rate;
+
+        // Optional surtax on earnings above a cumulative threshold.
+        if let (Some(additional_rate), Some(threshold)) = (
+            law.rules.get("additional_rate").and_then(|v| v.as_f64()),
+            law.rules.get("threshold").and_then(|v| v.as_f64()),
+        ) {
+            let over = (new_ytd - threshold).max(0.0) - (prior_ytd - threshold).max(0.0);
+            tax += over * additional_rate;
+        }
+
+        tax
+    }
+}
+
+/// Instantiate the tax calculator appropriate to a specific law.
+///
+/// The concrete calculator is chosen by the shape of the law's rules
+/// (see [`TaxRules`]): graduated-bracket laws get a [`BracketCalculator`],
+/// wage-base-capped laws a [`CappedCalculator`], and everything else a
+/// flat-rate calculator — the federal calculator for `US-FED`, a
+/// [`FlatStateCalculator`] otherwise.  Selecting the calculator from the
+/// law itself (rather than from a region-keyed table) keeps dispatch in
+/// step with the law version chosen for the pay period, so a flat stub
+/// and a bracket revision of the same region each get the right engine.
+pub fn calculator_for(law: &TaxLaw) -> Arc<dyn TaxCalculator> {
+    match serde_json::from_value::<TaxRules>(law.rules.clone()) {
+        Ok(TaxRules::Brackets(_)) => Arc::new(BracketCalculator {
+            region: law.region.clone(),
+        }),
+        Ok(TaxRules::Capped(_)) => Arc::new(CappedCalculator {
+            region: law.region.clone(),
+        }),
+        _ if law.region == "US-FED" => Arc::new(UsFederalCalculator),
+        _ => Arc::new(FlatStateCalculator {
+            region: law.region.clone(),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +500,8 @@ mod tests {
         let law = TaxLaw {
             region: "US-FED".into(),
             version: "2025".into(),
+            effective_from: None,
+            effective_to: None,
             rules: json!({"rate": 0.1}),
         };
         let employee = Employee {
@@ -143,8 +510,101 @@ mod tests {
             home_region: "US-OK".into(),
             pay_rate: 100.0,
             pay_frequency: PayFrequency::Salary,
+            currency: "USD".into(),
         };
         let tax = calc.calculate(&employee, 1000.0, &law);
         assert_eq!(tax, 100.0);
     }
+
+    #[test]
+    fn test_bracket_calculator() {
+        let calc = BracketCalculator {
+            region: "US-FED".into(),
+        };
+        let law = TaxLaw {
+            region: "US-FED".into(),
+            version: "2025".into(),
+            effective_from: None,
+            effective_to: None,
+            rules: json!({
+                "standard_deduction": 0.0,
+                "periods_per_year": 1.0,
+                "brackets": [
+                    {"upto": 1000.0, "rate": 0.10},
+                    {"upto": 2000.0, "rate": 0.20},
+                    {"upto": null, "rate": 0.30}
+                ]
+            }),
+        };
+        let employee = Employee {
+            id: "1".into(),
+            name: "Test".into(),
+            home_region: "US-FED".into(),
+            pay_rate: 2500.0,
+            pay_frequency: PayFrequency::Salary,
+            currency: "USD".into(),
+        };
+        // 1000 * 0.10 + 1000 * 0.20 + 500 * 0.30 = 100 + 200 + 150 = 450
+        let tax = calc.calculate(&employee, 2500.0, &law);
+        assert_eq!(tax, 450.0);
+    }
+
+    #[test]
+    fn test_bracket_calculator_flat_fallback() {
+        let calc = BracketCalculator {
+            region: "US-OK".into(),
+        };
+        let law = TaxLaw {
+            region: "US-OK".into(),
+            version: "2025".into(),
+            effective_from: None,
+            effective_to: None,
+            rules: json!({"rate": 0.05}),
+        };
+        let employee = Employee {
+            id: "1".into(),
+            name: "Test".into(),
+            home_region: "US-OK".into(),
+            pay_rate: 1000.0,
+            pay_frequency: PayFrequency::Salary,
+            currency: "USD".into(),
+        };
+        let tax = calc.calculate(&employee, 1000.0, &law);
+        assert_eq!(tax, 50.0);
+    }
+
+    #[test]
+    fn test_capped_calculator_respects_wage_base() {
+        let calc = CappedCalculator {
+            region: "US-SS".into(),
+        };
+        let law = TaxLaw {
+            region: "US-SS".into(),
+            version: "2025".into(),
+            effective_from: None,
+            effective_to: None,
+            rules: json!({"rate": 0.10, "wage_base": 1000.0}),
+        };
+        let employee = Employee {
+            id: "1".into(),
+            name: "Test".into(),
+            home_region: "US-SS".into(),
+            pay_rate: 600.0,
+            pay_frequency: PayFrequency::Salary,
+            currency: "USD".into(),
+        };
+        let mut prior = YtdTotals::default();
+        prior.gross = 600.0;
+        // Only 400 of the 600 gross falls below the 1000 wage base.
+        let tax = calc.calculate_with_ytd(&employee, 600.0, &law, &prior);
+        assert_eq!(tax, 40.0);
+    }
+
+    #[test]
+    fn test_tax_rules_reject_unknown_fields() {
+        // A well-formed flat rule validates.
+        assert!(serde_json::from_value::<TaxRules>(json!({"rate": 0.1})).is_ok());
+        // A misspelled field is rejected rather than silently ignored.
+        assert!(serde_json::from_value::<TaxRules>(json!({"rat": 0.1})).is_err());
+    }
 }
\ No newline at end of file